@@ -0,0 +1,87 @@
+use crate::repository::{GitRepository, RealGitRepository};
+use collections::HashMap;
+use parking_lot::Mutex;
+use std::path::{Path, PathBuf};
+
+/// Caches opened `GitRepository` handles by their canonicalized git-dir,
+/// so that sibling and descendant directories belonging to the same
+/// repository reuse a single `RealGitRepository` handle instead of each
+/// opening its own. Every lookup still runs `gix::discover` on the given
+/// path to find the enclosing git-dir; only the opened handle is cached.
+///
+/// Nothing in this tree outside this module constructs a `GitCache`: there
+/// is no worktree/workspace crate here that holds a per-directory
+/// `RealGitRepository` handle, and `manages`/`in_dot_git` below are
+/// exercised only by this module's own test.
+#[derive(Default)]
+pub struct GitCache {
+    repositories: Mutex<HashMap<PathBuf, Box<dyn GitRepository>>>,
+}
+
+impl GitCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the `GitRepository` that contains `path`. The enclosing
+    /// git-dir is discovered on every call; the repository is opened and
+    /// cached only the first time that git-dir has been seen.
+    pub fn repo_for_path(&self, path: &Path) -> Option<Box<dyn GitRepository>> {
+        let dotgit_path = gix::discover(path)
+            .ok()
+            .and_then(|repo| repo.git_dir().canonicalize().ok())?;
+
+        if let Some(repo) = self.repositories.lock().get(&dotgit_path) {
+            return Some(repo.boxed_clone());
+        }
+
+        let repo = RealGitRepository::open(&dotgit_path)?;
+        let cached = repo.boxed_clone();
+        self.repositories.lock().insert(dotgit_path, repo);
+        Some(cached)
+    }
+
+    /// Returns whether `path` is managed by the repository enclosing it.
+    ///
+    /// Not called from anywhere in this tree outside this module's test (see
+    /// the struct docs). Each call discovers the enclosing repository anew;
+    /// directories belonging to the same repository share the cached handle,
+    /// not the discovery.
+    pub fn manages(&self, path: &Path) -> bool {
+        self.repo_for_path(path)
+            .map(|repo| repo.manages(path))
+            .unwrap_or(false)
+    }
+
+    /// Returns whether `path` lives inside the `.git` directory of the
+    /// repository enclosing it.
+    pub fn in_dot_git(&self, path: &Path) -> bool {
+        self.repo_for_path(path)
+            .map(|repo| repo.in_dot_git(path))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repo_for_path_resolves_sibling_paths_to_the_same_repo() {
+        let root = std::env::temp_dir().join(format!("git_cache_test_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+        gix::init(&root).expect("gix init");
+
+        let cache = GitCache::new();
+        let top_level = cache.repo_for_path(&root).expect("repo discovered");
+        let nested = cache
+            .repo_for_path(&root.join("nested"))
+            .expect("nested path resolves to the same repo");
+
+        assert_eq!(top_level.git_dir_path(), nested.git_dir_path());
+        assert!(cache.manages(&root.join("nested")));
+        assert!(cache.in_dot_git(&root.join(".git").join("HEAD")));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,2 @@
+pub mod git_cache;
+pub mod repository;
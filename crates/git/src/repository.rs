@@ -1,6 +1,6 @@
-use anyhow::Result;
-use collections::HashMap;
-use git2::Repository as LibGitRepository;
+use anyhow::{anyhow, Result};
+use collections::{HashMap, HashSet};
+use gix::bstr::ByteSlice;
 use parking_lot::Mutex;
 use std::{
     path::{Path, PathBuf},
@@ -24,11 +24,102 @@ pub trait GitRepository: Send + Sync + std::fmt::Debug {
 
     fn reopen_git_repo(&mut self) -> bool;
 
-    fn git_repo(&self) -> Arc<Mutex<LibGitRepository>>;
+    /// Returns a cheap-to-clone, thread-safe handle to the underlying
+    /// repository, for callers that need lower-level access than this
+    /// trait exposes.
+    fn git_repo(&self) -> gix::ThreadSafeRepository;
 
     fn boxed_clone(&self) -> Box<dyn GitRepository>;
 
     async fn load_head_text(&self, relative_file_path: &Path) -> Option<String>;
+
+    /// Returns the working-tree status of a single path, or `None` if the
+    /// path is unmodified or not known to the repository.
+    fn status(&self, relative_path: &Path) -> Option<GitFileStatus>;
+
+    /// Returns the working-tree status of every path the repository
+    /// currently considers modified, staged, or untracked.
+    fn statuses(&self) -> HashMap<PathBuf, GitFileStatus>;
+
+    /// Returns the short name of the branch `HEAD` points at, or `None` if
+    /// `HEAD` is detached.
+    fn head_branch(&self) -> Option<String>;
+
+    /// Returns how far the current branch has diverged from its upstream,
+    /// or `None` if the branch has no configured upstream.
+    fn upstream_tracking(&self) -> Option<BranchAheadBehind>;
+
+    /// Returns the changed-line ranges between the staged/HEAD blob for
+    /// `relative_path` and `working_text`, for painting gutter change
+    /// indicators.
+    fn diff_hunks(&self, relative_path: &Path, working_text: &str) -> Vec<DiffHunk>;
+
+    /// Returns the repository's stash list, most recently pushed first.
+    fn stash_entries(&self) -> Vec<StashEntry>;
+
+    /// Returns whether the repository is mid-operation (merge, rebase,
+    /// etc.), so the UI can warn before destructive actions.
+    fn repo_state(&self) -> RepoState;
+}
+
+/// A single entry in the repository's stash, as enumerated by
+/// `stash_entries`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StashEntry {
+    pub index: usize,
+    pub message: String,
+    pub oid: String,
+}
+
+/// Coarse repository-level state, covering whether the repo is mid some
+/// multi-step operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RepoState {
+    #[default]
+    Clean,
+    Merge,
+    Rebase,
+    CherryPick,
+    Bisect,
+    Revert,
+}
+
+/// A contiguous range of changed lines between the `HEAD` blob and the
+/// working-tree text of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub old_lines: std::ops::Range<u32>,
+    pub new_lines: std::ops::Range<u32>,
+    pub kind: DiffHunkKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffHunkKind {
+    Added,
+    Modified,
+    Removed,
+}
+
+/// How far a local branch has diverged from its upstream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchAheadBehind {
+    pub ahead: usize,
+    pub behind: usize,
+    pub upstream_name: Option<String>,
+}
+
+/// The state of a single path relative to the index and `HEAD`, mirroring
+/// the categories a `git status` prompt distinguishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GitFileStatus {
+    /// The path has unresolved merge conflicts.
+    Conflicted,
+    /// The path has been added, modified, renamed, or deleted in the index.
+    Staged,
+    /// The path differs from the index in the working tree.
+    Modified,
+    /// The path is not tracked by the repository.
+    Untracked,
 }
 
 #[derive(Clone)]
@@ -39,22 +130,30 @@ pub struct RealGitRepository {
     // Note: if .git is a file, this points to the folder indicated by the .git file
     git_dir_path: Arc<Path>,
     scan_id: usize,
-    libgit_repository: Arc<Mutex<LibGitRepository>>,
+    // `gix::ThreadSafeRepository` clones cheaply and performs its own
+    // internal synchronization, so reads from multiple worktrees no longer
+    // serialize behind a single mutex the way libgit2 access used to.
+    repo: gix::ThreadSafeRepository,
 }
 
 impl RealGitRepository {
     pub fn open(dotgit_path: &Path) -> Option<Box<dyn GitRepository>> {
-        LibGitRepository::open(&dotgit_path)
+        gix::open(dotgit_path)
             .log_err()
-            .and_then::<Box<dyn GitRepository>, _>(|libgit_repository| {
+            .and_then::<Box<dyn GitRepository>, _>(|repo| {
+                let content_path = repo.work_dir()?.to_path_buf();
                 Some(Box::new(Self {
-                    content_path: libgit_repository.workdir()?.into(),
+                    content_path: content_path.into(),
                     git_dir_path: dotgit_path.canonicalize().log_err()?.into(),
                     scan_id: 0,
-                    libgit_repository: Arc::new(parking_lot::Mutex::new(libgit_repository)),
+                    repo: repo.into_sync(),
                 }))
             })
     }
+
+    fn local_repo(&self) -> gix::Repository {
+        self.repo.to_thread_local()
+    }
 }
 
 #[async_trait::async_trait]
@@ -84,20 +183,20 @@ impl GitRepository for RealGitRepository {
     }
 
     async fn load_head_text(&self, relative_file_path: &Path) -> Option<String> {
-        fn logic(repo: &LibGitRepository, relative_file_path: &Path) -> Result<Option<String>> {
-            const STAGE_NORMAL: i32 = 0;
-            let index = repo.index()?;
-            let oid = match index.get_path(relative_file_path, STAGE_NORMAL) {
-                Some(entry) => entry.id,
-                None => return Ok(None),
+        fn logic(repo: &gix::Repository, relative_file_path: &Path) -> Result<Option<String>> {
+            let index = repo.index_or_empty()?;
+            let Some(entry) = index.entry_by_path(&gix::path::to_unix_separators_on_windows(
+                gix::path::into_bstr(relative_file_path),
+            )) else {
+                return Ok(None);
             };
 
-            let content = repo.find_blob(oid)?.content().to_owned();
+            let content = repo.find_object(entry.id)?.detach().data;
             let head_text = String::from_utf8(content)?;
             Ok(Some(head_text))
         }
 
-        match logic(&self.libgit_repository.as_ref().lock(), relative_file_path) {
+        match logic(&self.local_repo(), relative_file_path) {
             Ok(value) => return value,
             Err(err) => log::error!("Error loading head text: {:?}", err),
         }
@@ -105,9 +204,11 @@ impl GitRepository for RealGitRepository {
     }
 
     fn reopen_git_repo(&mut self) -> bool {
-        match LibGitRepository::open(&self.git_dir_path) {
+        // `gix::open` wants something that converts into a `PathBuf`, which
+        // `&Arc<Path>` does not; go through the `Path` it derefs to instead.
+        match gix::open(self.git_dir_path.to_path_buf()) {
             Ok(repo) => {
-                self.libgit_repository = Arc::new(Mutex::new(repo));
+                self.repo = repo.into_sync();
                 true
             }
 
@@ -115,8 +216,8 @@ impl GitRepository for RealGitRepository {
         }
     }
 
-    fn git_repo(&self) -> Arc<Mutex<LibGitRepository>> {
-        self.libgit_repository.clone()
+    fn git_repo(&self) -> gix::ThreadSafeRepository {
+        self.repo.clone()
     }
 
     fn set_scan_id(&mut self, scan_id: usize) {
@@ -126,6 +227,205 @@ impl GitRepository for RealGitRepository {
     fn boxed_clone(&self) -> Box<dyn GitRepository> {
         Box::new(self.clone())
     }
+
+    fn status(&self, relative_path: &Path) -> Option<GitFileStatus> {
+        fn logic(repo: &gix::Repository, relative_path: &Path) -> Result<Option<GitFileStatus>> {
+            let pattern = gix::path::to_unix_separators_on_windows(gix::path::into_bstr(
+                relative_path,
+            ))
+            .into_owned();
+            let mut statuses = repo
+                .status(gix::progress::Discard)?
+                .untracked_files(gix::status::UntrackedFiles::Files)
+                .into_index_worktree_iter(Some(pattern.clone()))?;
+
+            if let Some(item) = statuses.next().transpose()? {
+                if let Some(status) = classify_status(&item) {
+                    return Ok(Some(status));
+                }
+            }
+
+            // The worktree matches the index for this path, so the only
+            // status left to find is a staged (HEAD-vs-index) change that
+            // `into_index_worktree_iter` never looks at.
+            if staged_paths(repo, Some(pattern.as_ref()))?.contains(relative_path) {
+                return Ok(Some(GitFileStatus::Staged));
+            }
+            Ok(None)
+        }
+
+        match logic(&self.local_repo(), relative_path) {
+            Ok(value) => value,
+            Err(err) => {
+                log::error!("Error computing status: {:?}", err);
+                None
+            }
+        }
+    }
+
+    fn statuses(&self) -> HashMap<PathBuf, GitFileStatus> {
+        fn logic(repo: &gix::Repository) -> Result<HashMap<PathBuf, GitFileStatus>> {
+            let mut result = HashMap::default();
+            let statuses = repo
+                .status(gix::progress::Discard)?
+                .untracked_files(gix::status::UntrackedFiles::Files)
+                .into_index_worktree_iter(None::<gix::bstr::BString>)?;
+
+            for item in statuses {
+                let item = item?;
+                let Some(status) = classify_status(&item) else {
+                    continue;
+                };
+                let path = PathBuf::from(item_rela_path(&item).to_string());
+                result.insert(path, status);
+            }
+
+            // Paths that are staged but otherwise clean in the worktree never
+            // show up in the index-worktree iterator above, so they need a
+            // separate HEAD-vs-index pass to be reported as `Staged` at all.
+            for path in staged_paths(repo, None)? {
+                result.entry(path).or_insert(GitFileStatus::Staged);
+            }
+            Ok(result)
+        }
+
+        match logic(&self.local_repo()) {
+            Ok(result) => result,
+            Err(err) => {
+                log::error!("Error computing statuses: {:?}", err);
+                HashMap::default()
+            }
+        }
+    }
+
+    fn head_branch(&self) -> Option<String> {
+        let repo = self.local_repo();
+        let head = repo.head().log_err()?;
+        head.referent_name()
+            .and_then(|name| name.shorten().to_str().ok().map(str::to_string))
+    }
+
+    fn upstream_tracking(&self) -> Option<BranchAheadBehind> {
+        fn logic(repo: &gix::Repository) -> Result<Option<BranchAheadBehind>> {
+            let head = repo.head()?;
+            let Some(reference_name) = head.referent_name() else {
+                return Ok(None);
+            };
+            let branch_name = reference_name.shorten().to_string();
+
+            let Some(local_id) = head.id() else {
+                return Ok(None);
+            };
+
+            // `branch_remote_tracking_ref_name` resolves to the local
+            // tracking ref (e.g. `refs/remotes/origin/main`) rather than
+            // handing back an upstream `Reference` directly, so it still has
+            // to be looked up to find the commit it currently points at.
+            let Some(upstream_name) = repo
+                .branch_remote_tracking_ref_name(
+                    reference_name.as_ref(),
+                    gix::remote::Direction::Fetch,
+                )
+                .transpose()?
+            else {
+                return Ok(None);
+            };
+            let upstream_display_name = upstream_name.shorten().to_string();
+            let upstream_id = repo.find_reference(upstream_name.as_ref())?.id().detach();
+
+            let (ahead, behind) = count_ahead_behind(repo, local_id.detach(), upstream_id)
+                .ok_or_else(|| anyhow!("failed to walk ahead/behind for {branch_name}"))?;
+
+            Ok(Some(BranchAheadBehind {
+                ahead,
+                behind,
+                upstream_name: Some(upstream_display_name),
+            }))
+        }
+
+        match logic(&self.local_repo()) {
+            Ok(value) => return value,
+            Err(err) => log::error!("Error computing upstream tracking: {:?}", err),
+        }
+        None
+    }
+
+    fn diff_hunks(&self, relative_path: &Path, working_text: &str) -> Vec<DiffHunk> {
+        fn logic(
+            repo: &gix::Repository,
+            relative_path: &Path,
+            working_text: &str,
+        ) -> Result<Vec<DiffHunk>> {
+            let index = repo.index_or_empty()?;
+            let old_text = match index.entry_by_path(&gix::path::to_unix_separators_on_windows(
+                gix::path::into_bstr(relative_path),
+            )) {
+                Some(entry) => {
+                    let content = repo.find_object(entry.id)?.detach().data;
+                    Some(String::from_utf8(content)?)
+                }
+                None => None,
+            };
+
+            Ok(multi_hunk_line_diff(
+                old_text.as_deref().unwrap_or(""),
+                working_text,
+            ))
+        }
+
+        match logic(&self.local_repo(), relative_path, working_text) {
+            Ok(hunks) => hunks,
+            Err(err) => {
+                log::error!("Error computing diff hunks: {:?}", err);
+                Vec::new()
+            }
+        }
+    }
+
+    fn stash_entries(&self) -> Vec<StashEntry> {
+        fn logic(repo: &gix::Repository) -> Result<Vec<StashEntry>> {
+            let Some(stash_ref) = repo.try_find_reference("refs/stash")? else {
+                return Ok(Vec::new());
+            };
+
+            // `refs/stash`'s reflog is appended to on every `git stash
+            // push`, so iterating it yields the oldest stash first.
+            // `stash_entries_newest_first` reverses that into the
+            // `stash@{0}` == most-recent convention this method documents.
+            let mut oldest_first = Vec::new();
+            for line in stash_ref.log_iter().all()?.into_iter().flatten() {
+                let line = line?;
+                oldest_first.push((line.message.to_string(), line.new_oid.to_string()));
+            }
+            Ok(stash_entries_newest_first(oldest_first))
+        }
+
+        match logic(&self.local_repo()) {
+            Ok(entries) => entries,
+            Err(err) => {
+                log::error!("Error reading stash entries: {:?}", err);
+                Vec::new()
+            }
+        }
+    }
+
+    fn repo_state(&self) -> RepoState {
+        let repo = self.local_repo();
+        match repo.state() {
+            Some(gix::state::InProgress::Merge) => RepoState::Merge,
+            Some(gix::state::InProgress::Rebase)
+            | Some(gix::state::InProgress::RebaseInteractive)
+            | Some(gix::state::InProgress::ApplyMailbox)
+            | Some(gix::state::InProgress::ApplyMailboxRebase) => RepoState::Rebase,
+            Some(gix::state::InProgress::CherryPick)
+            | Some(gix::state::InProgress::CherryPickSequence) => RepoState::CherryPick,
+            Some(gix::state::InProgress::Bisect) => RepoState::Bisect,
+            Some(gix::state::InProgress::Revert) | Some(gix::state::InProgress::RevertSequence) => {
+                RepoState::Revert
+            }
+            None => RepoState::Clean,
+        }
+    }
 }
 
 impl std::fmt::Debug for RealGitRepository {
@@ -134,7 +434,7 @@ impl std::fmt::Debug for RealGitRepository {
             .field("content_path", &self.content_path)
             .field("git_dir_path", &self.git_dir_path)
             .field("scan_id", &self.scan_id)
-            .field("libgit_repository", &"LibGitRepository")
+            .field("repo", &"gix::ThreadSafeRepository")
             .finish()
     }
 }
@@ -150,6 +450,16 @@ pub struct FakeGitRepository {
 #[derive(Debug, Clone, Default)]
 pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
+    /// The on-disk contents of the working tree, used to derive `Modified`
+    /// and `Untracked` statuses by comparison against `index_contents`.
+    pub worktree_contents: HashMap<PathBuf, String>,
+    /// Statuses that can't be derived from a plain content diff (staged
+    /// renames/deletes, merge conflicts); tests set these directly.
+    pub manual_statuses: HashMap<PathBuf, GitFileStatus>,
+    pub head_branch: Option<String>,
+    pub upstream_tracking: Option<BranchAheadBehind>,
+    pub stash_entries: Vec<StashEntry>,
+    pub repo_state: RepoState,
 }
 
 impl FakeGitRepository {
@@ -198,7 +508,7 @@ impl GitRepository for FakeGitRepository {
         true
     }
 
-    fn git_repo(&self) -> Arc<Mutex<LibGitRepository>> {
+    fn git_repo(&self) -> gix::ThreadSafeRepository {
         unimplemented!()
     }
 
@@ -209,4 +519,631 @@ impl GitRepository for FakeGitRepository {
     fn boxed_clone(&self) -> Box<dyn GitRepository> {
         Box::new(self.clone())
     }
+
+    fn status(&self, relative_path: &Path) -> Option<GitFileStatus> {
+        self.statuses().remove(relative_path)
+    }
+
+    fn statuses(&self) -> HashMap<PathBuf, GitFileStatus> {
+        let state = self.state.lock();
+
+        let mut result = HashMap::default();
+        for (path, worktree_text) in &state.worktree_contents {
+            let status = match state.index_contents.get(path) {
+                Some(index_text) if index_text == worktree_text => continue,
+                Some(_) => GitFileStatus::Modified,
+                None => GitFileStatus::Untracked,
+            };
+            result.insert(path.clone(), status);
+        }
+        for (path, status) in &state.manual_statuses {
+            result.insert(path.clone(), *status);
+        }
+        result
+    }
+
+    fn head_branch(&self) -> Option<String> {
+        self.state.lock().head_branch.clone()
+    }
+
+    fn upstream_tracking(&self) -> Option<BranchAheadBehind> {
+        self.state.lock().upstream_tracking.clone()
+    }
+
+    fn diff_hunks(&self, relative_path: &Path, working_text: &str) -> Vec<DiffHunk> {
+        let state = self.state.lock();
+        let head_text = state
+            .index_contents
+            .get(relative_path)
+            .map(String::as_str)
+            .unwrap_or("");
+        line_diff(head_text, working_text)
+    }
+
+    fn stash_entries(&self) -> Vec<StashEntry> {
+        self.state.lock().stash_entries.clone()
+    }
+
+    fn repo_state(&self) -> RepoState {
+        self.state.lock().repo_state
+    }
+}
+
+/// Maps a `gix` index-worktree status entry onto our coarser
+/// `GitFileStatus`, shared by the single-path and bulk lookups on
+/// `RealGitRepository`. Returns `None` for entries that don't represent a
+/// user-visible change, such as the index-only bookkeeping updates gix
+/// surfaces via `EntryStatus::NeedsUpdate`.
+fn classify_status(item: &gix::status::index_worktree::iter::Item) -> Option<GitFileStatus> {
+    use gix::status::index_worktree::iter::Summary;
+
+    match item.summary()? {
+        Summary::Conflict => Some(GitFileStatus::Conflicted),
+        // `IntentToAdd` records a `git add -N` placeholder: an index entry
+        // with no real content yet, which is the closest this iterator gets
+        // to a "staged" addition.
+        Summary::IntentToAdd => Some(GitFileStatus::Staged),
+        Summary::Added => Some(GitFileStatus::Untracked),
+        Summary::Removed
+        | Summary::Modified
+        | Summary::TypeChange
+        | Summary::Renamed
+        | Summary::Copied => Some(GitFileStatus::Modified),
+    }
+}
+
+/// Returns the repository-relative path of a status item, regardless of
+/// which variant produced it.
+fn item_rela_path(item: &gix::status::index_worktree::iter::Item) -> &gix::bstr::BStr {
+    use gix::status::index_worktree::iter::Item;
+
+    match item {
+        Item::Modification { rela_path, .. } => rela_path.as_ref(),
+        Item::DirectoryContents { entry, .. } => entry.rela_path.as_ref(),
+        Item::Rewrite { dirwalk_entry, .. } => dirwalk_entry.rela_path.as_ref(),
+    }
+}
+
+/// Returns the repository-relative paths that differ between `HEAD^{tree}`
+/// and the index, i.e. changes that have been staged with `git add`. Scoped
+/// to `pattern` when given, otherwise covers the whole repository.
+///
+/// This is a separate comparison from `into_index_worktree_iter`, which only
+/// diffs the index against the worktree and so never reports an ordinary
+/// staged add/modify/rename/delete once the worktree catches back up to the
+/// index.
+fn staged_paths(
+    repo: &gix::Repository,
+    pattern: Option<&gix::bstr::BStr>,
+) -> Result<HashSet<PathBuf>> {
+    let tree_id = repo.head_tree_id_or_empty()?;
+    let index = repo.index_or_empty()?;
+
+    let mut pathspec = match pattern {
+        Some(pattern) => Some(repo.pathspec(
+            false,
+            Some(pattern),
+            true,
+            &index,
+            gix::worktree::stack::state::attributes::Source::IdMapping,
+        )?),
+        None => None,
+    };
+
+    let mut staged = HashSet::default();
+    repo.tree_index_status(
+        &tree_id,
+        &index,
+        pathspec.as_mut(),
+        gix::status::tree_index::TrackRenames::AsConfigured,
+        |change, _tree_index, _worktree_index| {
+            staged.insert(PathBuf::from(change.location().to_string()));
+            Ok::<_, anyhow::Error>(gix::diff::index::Action::Continue(()))
+        },
+    )?;
+    Ok(staged)
+}
+
+/// A minimal prefix/suffix line diff used only by `FakeGitRepository`. It
+/// collapses every change into a single hunk, which is fine for asserting
+/// on deterministic test fixtures but is not precise enough for painting
+/// gutter marks, so `RealGitRepository` uses `multi_hunk_line_diff` instead.
+fn line_diff(old_text: &str, new_text: &str) -> Vec<DiffHunk> {
+    let old_lines: Vec<&str> = old_text.lines().collect();
+    let new_lines: Vec<&str> = new_text.lines().collect();
+
+    let common_prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let common_suffix = old_lines[common_prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[common_prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    let old_start = common_prefix;
+    let old_end = old_lines.len() - common_suffix;
+    let new_start = common_prefix;
+    let new_end = new_lines.len() - common_suffix;
+    if old_start == old_end && new_start == new_end {
+        return Vec::new();
+    }
+
+    let kind = if old_start == old_end {
+        DiffHunkKind::Added
+    } else if new_start == new_end {
+        DiffHunkKind::Removed
+    } else {
+        DiffHunkKind::Modified
+    };
+    vec![DiffHunk {
+        old_lines: old_start as u32..old_end as u32,
+        new_lines: new_start as u32..new_end as u32,
+        kind,
+    }]
+}
+
+/// Counts commits reachable from `local` but not `upstream` (ahead) and vice
+/// versa (behind). `gix` has no `graph_ahead_behind` equivalent to git2's, so
+/// this finds the merge-base of the two tips and walks each tip only down to
+/// that boundary (like `git rev-list <base>..<tip>`), rather than the full
+/// ancestry of either side.
+fn count_ahead_behind(
+    repo: &gix::Repository,
+    local: gix::ObjectId,
+    upstream: gix::ObjectId,
+) -> Option<(usize, usize)> {
+    if local == upstream {
+        return Some((0, 0));
+    }
+
+    // If the two tips share no history at all, `merge_base` fails and we
+    // fall back to walking each side in full, same as `git` would when
+    // diffing unrelated histories.
+    let boundary = repo.merge_base(local, upstream).ok().map(|id| id.detach());
+
+    let count_unique_to = |tip: gix::ObjectId| -> Option<usize> {
+        let mut walk = repo.rev_walk([tip]);
+        if let Some(boundary) = boundary {
+            walk = walk.with_hidden([boundary]);
+        }
+        Some(walk.all().ok()?.filter_map(Result::ok).count())
+    };
+
+    Some((count_unique_to(local)?, count_unique_to(upstream)?))
+}
+
+/// A line-level diff producing one hunk per contiguous changed region, the
+/// way `git diff` hunks a file: two edits separated by unchanged lines
+/// produce two hunks, not one. Delegates to `imara_diff`'s linear-space
+/// Myers/Histogram implementation (the same family of algorithm `git` and
+/// `gix` use internally) rather than a quadratic DP table, so this stays
+/// cheap on the large, mostly-unchanged files an editor diffs against HEAD.
+fn multi_hunk_line_diff(old_text: &str, new_text: &str) -> Vec<DiffHunk> {
+    // `InternedInput` lives under `imara_diff::intern`, not the crate root.
+    let input = imara_diff::intern::InternedInput::new(old_text, new_text);
+    imara_diff::diff(
+        imara_diff::Algorithm::Histogram,
+        &input,
+        DiffHunkSink::default(),
+    )
+}
+
+#[derive(Default)]
+struct DiffHunkSink {
+    hunks: Vec<DiffHunk>,
+}
+
+impl imara_diff::Sink for DiffHunkSink {
+    type Out = Vec<DiffHunk>;
+
+    fn process_change(&mut self, old_lines: std::ops::Range<u32>, new_lines: std::ops::Range<u32>) {
+        let kind = if old_lines.is_empty() {
+            DiffHunkKind::Added
+        } else if new_lines.is_empty() {
+            DiffHunkKind::Removed
+        } else {
+            DiffHunkKind::Modified
+        };
+        self.hunks.push(DiffHunk {
+            old_lines,
+            new_lines,
+            kind,
+        });
+    }
+
+    fn finish(self) -> Self::Out {
+        self.hunks
+    }
+}
+
+/// Converts `(message, oid)` pairs read from `refs/stash`'s reflog in file
+/// order (oldest first) into `StashEntry`s indexed newest-first, matching
+/// the `stash@{0}` == most-recent convention `git stash list` uses.
+fn stash_entries_newest_first(oldest_first: Vec<(String, String)>) -> Vec<StashEntry> {
+    oldest_first
+        .into_iter()
+        .rev()
+        .enumerate()
+        .map(|(index, (message, oid))| StashEntry { index, message, oid })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_repo(state: FakeGitRepositoryState) -> Box<dyn GitRepository> {
+        FakeGitRepository::open(
+            Path::new("/fake/.git"),
+            0,
+            Arc::new(Mutex::new(state)),
+        )
+    }
+
+    #[test]
+    fn statuses_classifies_modified_and_untracked_paths() {
+        let repo = fake_repo(FakeGitRepositoryState {
+            index_contents: HashMap::from_iter([
+                (PathBuf::from("unchanged.rs"), "fn a() {}".to_string()),
+                (PathBuf::from("modified.rs"), "fn a() {}".to_string()),
+            ]),
+            worktree_contents: HashMap::from_iter([
+                (PathBuf::from("unchanged.rs"), "fn a() {}".to_string()),
+                (PathBuf::from("modified.rs"), "fn a() { /* changed */ }".to_string()),
+                (PathBuf::from("new.rs"), "fn b() {}".to_string()),
+            ]),
+            ..Default::default()
+        });
+
+        let statuses = repo.statuses();
+        assert_eq!(statuses.get(Path::new("unchanged.rs")), None);
+        assert_eq!(
+            statuses.get(Path::new("modified.rs")),
+            Some(&GitFileStatus::Modified)
+        );
+        assert_eq!(
+            statuses.get(Path::new("new.rs")),
+            Some(&GitFileStatus::Untracked)
+        );
+    }
+
+    #[test]
+    fn statuses_prefers_manual_overrides() {
+        let repo = fake_repo(FakeGitRepositoryState {
+            // Without the override these contents would derive `Modified`.
+            index_contents: HashMap::from_iter([(
+                PathBuf::from("conflicted.rs"),
+                "fn a() {}".to_string(),
+            )]),
+            worktree_contents: HashMap::from_iter([(
+                PathBuf::from("conflicted.rs"),
+                "fn a() { /* changed */ }".to_string(),
+            )]),
+            manual_statuses: HashMap::from_iter([(
+                PathBuf::from("conflicted.rs"),
+                GitFileStatus::Conflicted,
+            )]),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            repo.status(Path::new("conflicted.rs")),
+            Some(GitFileStatus::Conflicted)
+        );
+    }
+
+    #[test]
+    fn head_branch_and_upstream_tracking_return_stored_state() {
+        let upstream_tracking = BranchAheadBehind {
+            ahead: 2,
+            behind: 1,
+            upstream_name: Some("origin/main".to_string()),
+        };
+        let repo = fake_repo(FakeGitRepositoryState {
+            head_branch: Some("main".to_string()),
+            upstream_tracking: Some(upstream_tracking.clone()),
+            ..Default::default()
+        });
+
+        assert_eq!(repo.head_branch(), Some("main".to_string()));
+        assert_eq!(repo.upstream_tracking(), Some(upstream_tracking));
+    }
+
+    #[test]
+    fn head_branch_and_upstream_tracking_default_to_none() {
+        let repo = fake_repo(FakeGitRepositoryState::default());
+
+        assert_eq!(repo.head_branch(), None);
+        assert_eq!(repo.upstream_tracking(), None);
+    }
+
+    #[test]
+    fn diff_hunks_reports_added_and_modified_ranges() {
+        let repo = fake_repo(FakeGitRepositoryState {
+            index_contents: HashMap::from_iter([(
+                PathBuf::from("a.rs"),
+                "one\ntwo\nthree\n".to_string(),
+            )]),
+            ..Default::default()
+        });
+
+        let hunks = repo.diff_hunks(Path::new("a.rs"), "one\ntwo\nTHREE\nfour\n");
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].old_lines, 2..3);
+        assert_eq!(hunks[0].new_lines, 2..4);
+        assert_eq!(hunks[0].kind, DiffHunkKind::Modified);
+    }
+
+    #[test]
+    fn diff_hunks_reports_pure_addition_for_untracked_file() {
+        let repo = fake_repo(FakeGitRepositoryState::default());
+
+        let hunks = repo.diff_hunks(Path::new("new.rs"), "one\ntwo\n");
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].old_lines, 0..0);
+        assert_eq!(hunks[0].new_lines, 0..2);
+        assert_eq!(hunks[0].kind, DiffHunkKind::Added);
+    }
+
+    #[test]
+    fn multi_hunk_line_diff_splits_unrelated_edits_into_separate_hunks() {
+        let old_text = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        let new_text = "a\nB\nc\nd\ne\nf\nG\nh\n";
+
+        let hunks = multi_hunk_line_diff(old_text, new_text);
+
+        assert_eq!(
+            hunks,
+            vec![
+                DiffHunk {
+                    old_lines: 1..2,
+                    new_lines: 1..2,
+                    kind: DiffHunkKind::Modified,
+                },
+                DiffHunk {
+                    old_lines: 6..7,
+                    new_lines: 6..7,
+                    kind: DiffHunkKind::Modified,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn stash_entries_newest_first_reverses_reflog_order() {
+        let entries = stash_entries_newest_first(vec![
+            ("oldest".to_string(), "oid1".to_string()),
+            ("middle".to_string(), "oid2".to_string()),
+            ("newest".to_string(), "oid3".to_string()),
+        ]);
+
+        assert_eq!(entries[0].index, 0);
+        assert_eq!(entries[0].message, "newest");
+        assert_eq!(entries.last().unwrap().message, "oldest");
+    }
+
+    #[test]
+    fn repo_state_reflects_fake_state() {
+        let repo = fake_repo(FakeGitRepositoryState {
+            repo_state: RepoState::Rebase,
+            ..Default::default()
+        });
+
+        assert_eq!(repo.repo_state(), RepoState::Rebase);
+    }
+}
+
+/// Exercises `RealGitRepository` against an actual `git`-managed working
+/// tree, using the `git` CLI to set up fixtures. Unlike `mod tests` above
+/// (which only drives `FakeGitRepository`), these catch the methods' use of
+/// the real `gix` API going stale against a new `gix` release.
+#[cfg(test)]
+mod real_repo_tests {
+    use super::*;
+
+    fn run_git(dir: &Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("git should be on PATH");
+        assert!(
+            output.status.success(),
+            "git {args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    fn init_real_repo(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("git_repository_test_{name}_{}", std::process::id()));
+        std::fs::remove_dir_all(&root).ok();
+        std::fs::create_dir_all(&root).unwrap();
+        run_git(&root, &["init", "-q", "-b", "main"]);
+        run_git(&root, &["config", "user.email", "test@example.com"]);
+        run_git(&root, &["config", "user.name", "Test"]);
+        root
+    }
+
+    fn open_real_repo(root: &Path) -> Box<dyn GitRepository> {
+        RealGitRepository::open(&root.join(".git")).expect("repo opens")
+    }
+
+    #[test]
+    fn status_and_statuses_report_real_worktree_changes() {
+        let root = init_real_repo("status");
+        std::fs::write(root.join("tracked.rs"), "fn a() {}\n").unwrap();
+        run_git(&root, &["add", "tracked.rs"]);
+        run_git(&root, &["commit", "-q", "-m", "init"]);
+
+        std::fs::write(root.join("tracked.rs"), "fn a() { /* changed */ }\n").unwrap();
+        std::fs::write(root.join("new.rs"), "fn b() {}\n").unwrap();
+
+        let repo = open_real_repo(&root);
+        assert_eq!(
+            repo.status(Path::new("tracked.rs")),
+            Some(GitFileStatus::Modified)
+        );
+        assert_eq!(
+            repo.status(Path::new("new.rs")),
+            Some(GitFileStatus::Untracked)
+        );
+
+        let statuses = repo.statuses();
+        assert_eq!(
+            statuses.get(Path::new("tracked.rs")),
+            Some(&GitFileStatus::Modified)
+        );
+        assert_eq!(
+            statuses.get(Path::new("new.rs")),
+            Some(&GitFileStatus::Untracked)
+        );
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn status_and_statuses_report_real_staged_changes() {
+        let root = init_real_repo("staged");
+        std::fs::write(root.join("tracked.rs"), "fn a() {}\n").unwrap();
+        run_git(&root, &["add", "tracked.rs"]);
+        run_git(&root, &["commit", "-q", "-m", "init"]);
+
+        // Staged-but-otherwise-clean changes: the worktree matches the index
+        // for both paths, so only a HEAD-vs-index comparison can find them.
+        std::fs::remove_file(root.join("tracked.rs")).unwrap();
+        run_git(&root, &["rm", "-q", "tracked.rs"]);
+        std::fs::write(root.join("added.rs"), "fn b() {}\n").unwrap();
+        run_git(&root, &["add", "added.rs"]);
+
+        let repo = open_real_repo(&root);
+        assert_eq!(
+            repo.status(Path::new("tracked.rs")),
+            Some(GitFileStatus::Staged)
+        );
+        assert_eq!(
+            repo.status(Path::new("added.rs")),
+            Some(GitFileStatus::Staged)
+        );
+
+        let statuses = repo.statuses();
+        assert_eq!(
+            statuses.get(Path::new("tracked.rs")),
+            Some(&GitFileStatus::Staged)
+        );
+        assert_eq!(
+            statuses.get(Path::new("added.rs")),
+            Some(&GitFileStatus::Staged)
+        );
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn head_branch_reports_real_branch_name() {
+        let root = init_real_repo("head-branch");
+        run_git(&root, &["commit", "-q", "--allow-empty", "-m", "init"]);
+
+        let repo = open_real_repo(&root);
+        assert_eq!(repo.head_branch(), Some("main".to_string()));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn upstream_tracking_counts_real_ahead_and_behind() {
+        let root = init_real_repo("upstream");
+        run_git(&root, &["commit", "-q", "--allow-empty", "-m", "A"]);
+        let a = run_git(&root, &["rev-parse", "HEAD"]);
+        run_git(&root, &["update-ref", "refs/remotes/origin/main", &a]);
+        run_git(&root, &["config", "branch.main.remote", "origin"]);
+        run_git(&root, &["config", "branch.main.merge", "refs/heads/main"]);
+
+        // Local `main` gains a commit the remote-tracking ref doesn't have...
+        run_git(&root, &["commit", "-q", "--allow-empty", "-m", "B"]);
+
+        // ...and the remote-tracking ref gains one local `main` doesn't have,
+        // so the branch is both ahead and behind by one commit.
+        run_git(&root, &["checkout", "-q", "-b", "tmp", &a]);
+        run_git(&root, &["commit", "-q", "--allow-empty", "-m", "D"]);
+        let d = run_git(&root, &["rev-parse", "HEAD"]);
+        run_git(&root, &["update-ref", "refs/remotes/origin/main", &d]);
+        run_git(&root, &["checkout", "-q", "main"]);
+
+        let repo = open_real_repo(&root);
+        let tracking = repo.upstream_tracking().expect("upstream is configured");
+        assert_eq!(tracking.ahead, 1);
+        assert_eq!(tracking.behind, 1);
+        assert_eq!(tracking.upstream_name.as_deref(), Some("origin/main"));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn diff_hunks_reports_real_modification() {
+        let root = init_real_repo("diff-hunks");
+        std::fs::write(root.join("a.rs"), "one\ntwo\nthree\n").unwrap();
+        run_git(&root, &["add", "a.rs"]);
+        run_git(&root, &["commit", "-q", "-m", "init"]);
+
+        let repo = open_real_repo(&root);
+        let hunks = repo.diff_hunks(Path::new("a.rs"), "one\ntwo\nTHREE\nfour\n");
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].old_lines, 2..3);
+        assert_eq!(hunks[0].new_lines, 2..4);
+        assert_eq!(hunks[0].kind, DiffHunkKind::Modified);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn stash_entries_reports_real_stash() {
+        let root = init_real_repo("stash");
+        std::fs::write(root.join("a.rs"), "one\n").unwrap();
+        run_git(&root, &["add", "a.rs"]);
+        run_git(&root, &["commit", "-q", "-m", "init"]);
+        std::fs::write(root.join("a.rs"), "two\n").unwrap();
+        run_git(&root, &["stash", "push", "-q", "-m", "wip"]);
+
+        let repo = open_real_repo(&root);
+        let entries = repo.stash_entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].index, 0);
+        assert!(entries[0].message.contains("wip"));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn repo_state_reports_real_merge_conflict() {
+        let root = init_real_repo("merge-conflict");
+        std::fs::write(root.join("a.rs"), "base\n").unwrap();
+        run_git(&root, &["add", "a.rs"]);
+        run_git(&root, &["commit", "-q", "-m", "base"]);
+
+        run_git(&root, &["checkout", "-q", "-b", "feature"]);
+        std::fs::write(root.join("a.rs"), "feature\n").unwrap();
+        run_git(&root, &["commit", "-q", "-am", "feature change"]);
+
+        run_git(&root, &["checkout", "-q", "main"]);
+        std::fs::write(root.join("a.rs"), "main\n").unwrap();
+        run_git(&root, &["commit", "-q", "-am", "main change"]);
+
+        // Expected to fail with a conflict, leaving MERGE_HEAD behind.
+        std::process::Command::new("git")
+            .args(["merge", "-q", "feature"])
+            .current_dir(&root)
+            .output()
+            .ok();
+
+        let repo = open_real_repo(&root);
+        assert_eq!(repo.repo_state(), RepoState::Merge);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
 }